@@ -0,0 +1,181 @@
+use super::{common::authoritative_config, payload::CloseBufferDecree};
+use crate::{
+    constants::UPGRADE_SEED_PREFIX, error::TokenBridgeError, legacy::instruction::EmptyArgs,
+    state::Config,
+};
+use anchor_lang::prelude::*;
+use core_bridge_program::sdk as core_bridge;
+use solana_program::{bpf_loader_upgradeable, program::invoke_signed};
+
+/// Enum tag for `UpgradeableLoaderState::Buffer`, i.e. the 4-byte little-endian discriminant the
+/// BPF Loader Upgradeable program writes at the start of a buffer account's data.
+const BUFFER_LOADER_STATE_TAG: u32 = 1;
+
+#[derive(Accounts)]
+pub struct CloseBuffer<'info> {
+    #[account(mut)]
+    payer: Signer<'info>,
+
+    /// VAA account, which may either be the new EncodedVaa account or legacy PostedVaaV1
+    /// account.
+    ///
+    /// CHECK: This account will be read via zero-copy deserialization in the instruction
+    /// handler, which will determine which type of VAA account is being used. If this account
+    /// is the legacy PostedVaaV1 account, its PDA address will be verified by this zero-copy
+    /// reader.
+    #[account(owner = core_bridge::id())]
+    vaa: AccountInfo<'info>,
+
+    /// Claim account (mut), which acts as replay protection after consuming data from the VAA
+    /// account.
+    ///
+    /// Seeds: [emitter_address, emitter_chain, sequence],
+    /// seeds::program = token_bridge_program.
+    ///
+    /// CHECK: This account is created via [claim_vaa](core_bridge_program::sdk::claim_vaa).
+    /// This account can only be created once for this VAA.
+    #[account(mut)]
+    claim: AccountInfo<'info>,
+
+    /// CHECK: This is the buffer's authority. We verify this PDA address here out of convenience
+    /// to get the PDA bump seed to invoke the BPF Loader Upgradeable program.
+    #[account(
+        seeds = [UPGRADE_SEED_PREFIX],
+        bump,
+    )]
+    upgrade_authority: AccountInfo<'info>,
+
+    /// Spill account to collect the buffer's reclaimed lamports.
+    ///
+    /// CHECK: This account receives all lamports held by the buffer once it is closed.
+    #[account(mut)]
+    spill: AccountInfo<'info>,
+
+    /// Abandoned buffer named by the governance VAA.
+    ///
+    /// CHECK: The pubkey of this account is checked in access control against the one encoded in
+    /// the governance VAA. Access control also deserializes this account's loader state to make
+    /// sure it is actually a `Buffer` and not, say, a `ProgramData` account.
+    #[account(mut)]
+    buffer: AccountInfo<'info>,
+
+    /// BPF Loader Upgradeable program.
+    ///
+    /// CHECK: In order to close the buffer, we need to invoke the BPF Loader Upgradeable
+    /// program.
+    #[account(address = solana_program::bpf_loader_upgradeable::id())]
+    bpf_loader_upgradeable_program: AccountInfo<'info>,
+
+    /// Program configuration account, which stores the governance chain/emitter this program
+    /// trusts.
+    ///
+    /// CHECK: This account may still be in its pre-migration layout, which only stored
+    /// `core_bridge_program`. [Config::migrate_and_load] brings it up to date in place before we
+    /// read the governance chain/emitter from it.
+    #[account(
+        mut,
+        seeds = [Config::SEED_PREFIX],
+        bump,
+    )]
+    config: AccountInfo<'info>,
+
+    system_program: Program<'info, System>,
+}
+
+impl<'info> core_bridge::legacy::ProcessLegacyInstruction<'info, EmptyArgs> for CloseBuffer<'info> {
+    const LOG_IX_NAME: &'static str = "LegacyCloseBuffer";
+
+    const ANCHOR_IX_FN: fn(Context<Self>, EmptyArgs) -> Result<()> = close_buffer;
+}
+
+impl<'info> CloseBuffer<'info> {
+    fn constraints(ctx: &Context<Self>) -> Result<()> {
+        let vaa_acc_info = &ctx.accounts.vaa;
+        let vaa = core_bridge::VaaAccount::load(vaa_acc_info)?;
+
+        // `authoritative_config` below is what authorizes this VAA -- it checks the VAA's
+        // emitter chain/address against `Config`, which is the actual, governance-updatable
+        // source of truth (see `update_governance_source.rs`). `CloseBuffer` is not one of the
+        // actions `crate::processor::require_valid_governance_vaa`'s payload parser understands
+        // anyway, so the decree itself is decoded straight from the VAA payload below.
+        let decree = CloseBufferDecree::parse(vaa.payload())
+            .ok_or(error!(TokenBridgeError::InvalidGovernanceAction))?;
+
+        // Make sure that this buffer closure is intended for this network.
+        require_eq!(
+            decree.chain(),
+            core_bridge::SOLANA_CHAIN,
+            TokenBridgeError::GovernanceForAnotherChain
+        );
+
+        // Read the buffer pubkey and check against the buffer in our account context.
+        require_keys_eq!(
+            Pubkey::from(decree.buffer()),
+            ctx.accounts.buffer.key(),
+            TokenBridgeError::ImplementationMismatch
+        );
+
+        // Restrict this handler to `Buffer` accounts. `Buffer`'s loader state is a 4-byte
+        // little-endian enum tag (`1`) followed by an `Option<Pubkey>` authority; we don't rely
+        // on the loader to reject other account kinds (e.g. `ProgramData`) for us.
+        let buffer_data = ctx.accounts.buffer.try_borrow_data()?;
+        require!(
+            buffer_data.len() >= 4,
+            TokenBridgeError::ImplementationMismatch
+        );
+        let loader_state_tag = u32::from_le_bytes(buffer_data[..4].try_into().unwrap());
+        require_eq!(
+            loader_state_tag,
+            BUFFER_LOADER_STATE_TAG,
+            TokenBridgeError::ImplementationMismatch
+        );
+        drop(buffer_data);
+
+        authoritative_config(
+            &ctx.accounts.config,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            &vaa,
+        )?;
+
+        // Done.
+        Ok(())
+    }
+}
+
+/// Processor for close-buffer governance decrees. This instruction handler invokes the BPF
+/// Loader Upgradeable program to close an abandoned upgrade buffer, reclaiming its rent to
+/// `spill`. Restricted to Buffer accounts; this cannot be used to close a program's
+/// `program_data` account.
+#[access_control(CloseBuffer::constraints(&ctx))]
+fn close_buffer(ctx: Context<CloseBuffer>, _args: EmptyArgs) -> Result<()> {
+    let vaa = core_bridge::VaaAccount::load(&ctx.accounts.vaa).unwrap();
+
+    // Create the claim account to provide replay protection. Because this instruction creates this
+    // account every time it is executed, this account cannot be created again with this emitter
+    // address, chain and sequence combination.
+    core_bridge::claim_vaa(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            core_bridge::ClaimVaa {
+                claim: ctx.accounts.claim.to_account_info(),
+                payer: ctx.accounts.payer.to_account_info(),
+            },
+        ),
+        &crate::ID,
+        &vaa,
+        None,
+    )?;
+
+    // Finally close the buffer, sending its lamports to spill.
+    invoke_signed(
+        &bpf_loader_upgradeable::close(
+            &ctx.accounts.buffer.key(),
+            &ctx.accounts.spill.key(),
+            &ctx.accounts.upgrade_authority.key(),
+        ),
+        &ctx.accounts.to_account_infos(),
+        &[&[UPGRADE_SEED_PREFIX, &[ctx.bumps["upgrade_authority"]]]],
+    )
+    .map_err(Into::into)
+}
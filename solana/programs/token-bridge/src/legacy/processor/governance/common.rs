@@ -0,0 +1,30 @@
+//! Helpers shared by the governance instruction handlers in this module.
+
+use crate::{error::TokenBridgeError, state::Config};
+use anchor_lang::prelude::*;
+use core_bridge_program::sdk as core_bridge;
+
+/// Loads `Config` (migrating it in place from the legacy layout if necessary) and checks that
+/// `vaa` was emitted by the governance chain/emitter `Config` trusts. Every governance handler
+/// in this module goes through this helper so that check isn't duplicated per handler.
+pub(super) fn authoritative_config<'info>(
+    config_account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    vaa: &core_bridge::VaaAccount,
+) -> Result<Config> {
+    let config = Config::migrate_and_load(config_account, payer, system_program)?;
+
+    require_eq!(
+        vaa.emitter_chain(),
+        config.governance_chain,
+        TokenBridgeError::InvalidGovernanceAction
+    );
+    require_keys_eq!(
+        Pubkey::from(vaa.emitter_address()),
+        config.governance_emitter,
+        TokenBridgeError::InvalidGovernanceAction
+    );
+
+    Ok(config)
+}
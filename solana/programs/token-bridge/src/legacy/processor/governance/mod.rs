@@ -0,0 +1,11 @@
+mod close_buffer;
+mod common;
+mod payload;
+mod set_upgrade_authority;
+mod update_governance_source;
+mod upgrade_contract;
+
+pub use close_buffer::*;
+pub use set_upgrade_authority::*;
+pub use update_governance_source::*;
+pub use upgrade_contract::*;
@@ -0,0 +1,217 @@
+//! Decoders for governance actions that the upstream governance payload parser (the one backing
+//! `contract_upgrade()`) does not know about. Each decree below follows the same
+//! `[module (32)][action (1)][chain (2)][..]` framing as every other token bridge governance
+//! action, so these are parsed straight out of the VAA payload rather than through that parser.
+
+/// Token Bridge governance module identifier, right-aligned in 32 bytes. Every token bridge
+/// governance decree, including `contract_upgrade`, is emitted under this module.
+fn governance_module() -> [u8; 32] {
+    let mut module = [0u8; 32];
+    let name = b"TokenBridge";
+    module[32 - name.len()..].copy_from_slice(name);
+    module
+}
+
+const ACTION_SET_UPGRADE_AUTHORITY: u8 = 4;
+const ACTION_CLOSE_BUFFER: u8 = 5;
+const ACTION_UPDATE_GOVERNANCE_SOURCE: u8 = 6;
+
+/// Decoded `SetUpgradeAuthority` governance decree: `{ chain: u16, new_authority: [u8; 32] }`.
+pub struct SetUpgradeAuthorityDecree {
+    chain: u16,
+    new_authority: [u8; 32],
+}
+
+impl SetUpgradeAuthorityDecree {
+    pub fn chain(&self) -> u16 {
+        self.chain
+    }
+
+    /// The requested new upgrade authority, or the zero pubkey if this decree asks the program
+    /// to relinquish upgrade authority and become immutable.
+    pub fn new_authority(&self) -> [u8; 32] {
+        self.new_authority
+    }
+
+    /// Parses a `SetUpgradeAuthority` decree out of a governance VAA's payload. Returns `None`
+    /// if the payload is not addressed to this module/action, mirroring how `contract_upgrade()`
+    /// signals "not this action" on the upstream payload type.
+    pub fn parse(payload: &[u8]) -> Option<Self> {
+        const LEN: usize = 32 + 1 + 2 + 32;
+
+        if payload.len() != LEN || payload[..32] != governance_module() {
+            return None;
+        }
+        if payload[32] != ACTION_SET_UPGRADE_AUTHORITY {
+            return None;
+        }
+
+        let chain = u16::from_be_bytes(payload[33..35].try_into().unwrap());
+        let mut new_authority = [0u8; 32];
+        new_authority.copy_from_slice(&payload[35..67]);
+
+        Some(Self {
+            chain,
+            new_authority,
+        })
+    }
+}
+
+/// Decoded `CloseBuffer` governance decree: `{ chain: u16, buffer: [u8; 32] }`.
+pub struct CloseBufferDecree {
+    chain: u16,
+    buffer: [u8; 32],
+}
+
+impl CloseBufferDecree {
+    pub fn chain(&self) -> u16 {
+        self.chain
+    }
+
+    pub fn buffer(&self) -> [u8; 32] {
+        self.buffer
+    }
+
+    /// Parses a `CloseBuffer` decree out of a governance VAA's payload. Returns `None` if the
+    /// payload is not addressed to this module/action.
+    pub fn parse(payload: &[u8]) -> Option<Self> {
+        const LEN: usize = 32 + 1 + 2 + 32;
+
+        if payload.len() != LEN || payload[..32] != governance_module() {
+            return None;
+        }
+        if payload[32] != ACTION_CLOSE_BUFFER {
+            return None;
+        }
+
+        let chain = u16::from_be_bytes(payload[33..35].try_into().unwrap());
+        let mut buffer = [0u8; 32];
+        buffer.copy_from_slice(&payload[35..67]);
+
+        Some(Self { chain, buffer })
+    }
+}
+
+/// Decoded `UpdateGovernanceSource` governance decree:
+/// `{ chain: u16, new_governance_chain: u16, new_governance_emitter: [u8; 32] }`. Repoints the
+/// governance chain/emitter `Config` trusts, which is what makes `Config` a genuine source of
+/// truth instead of a permanent mirror of the compile-time defaults it's seeded with.
+pub struct UpdateGovernanceSourceDecree {
+    chain: u16,
+    new_governance_chain: u16,
+    new_governance_emitter: [u8; 32],
+}
+
+impl UpdateGovernanceSourceDecree {
+    pub fn chain(&self) -> u16 {
+        self.chain
+    }
+
+    pub fn new_governance_chain(&self) -> u16 {
+        self.new_governance_chain
+    }
+
+    pub fn new_governance_emitter(&self) -> [u8; 32] {
+        self.new_governance_emitter
+    }
+
+    /// Parses an `UpdateGovernanceSource` decree out of a governance VAA's payload. Returns
+    /// `None` if the payload is not addressed to this module/action.
+    pub fn parse(payload: &[u8]) -> Option<Self> {
+        const LEN: usize = 32 + 1 + 2 + 2 + 32;
+
+        if payload.len() != LEN || payload[..32] != governance_module() {
+            return None;
+        }
+        if payload[32] != ACTION_UPDATE_GOVERNANCE_SOURCE {
+            return None;
+        }
+
+        let chain = u16::from_be_bytes(payload[33..35].try_into().unwrap());
+        let new_governance_chain = u16::from_be_bytes(payload[35..37].try_into().unwrap());
+        let mut new_governance_emitter = [0u8; 32];
+        new_governance_emitter.copy_from_slice(&payload[37..69]);
+
+        Some(Self {
+            chain,
+            new_governance_chain,
+            new_governance_emitter,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn governance_payload(action: u8, rest: &[u8]) -> Vec<u8> {
+        let mut payload = governance_module().to_vec();
+        payload.push(action);
+        payload.extend_from_slice(rest);
+        payload
+    }
+
+    #[test]
+    fn set_upgrade_authority_decree_round_trips() {
+        let new_authority = [7u8; 32];
+        let mut rest = vec![0u8, 1]; // chain = 1 (Solana)
+        rest.extend_from_slice(&new_authority);
+        let payload = governance_payload(ACTION_SET_UPGRADE_AUTHORITY, &rest);
+
+        let decree = SetUpgradeAuthorityDecree::parse(&payload).unwrap();
+        assert_eq!(decree.chain(), 1);
+        assert_eq!(decree.new_authority(), new_authority);
+    }
+
+    #[test]
+    fn set_upgrade_authority_decree_round_trips_immutable_request() {
+        // The zero authority is how a decree asks the program to relinquish upgrade authority
+        // and become immutable, as opposed to rotating to a new authority.
+        let mut rest = vec![0u8, 1];
+        rest.extend_from_slice(&[0u8; 32]);
+        let payload = governance_payload(ACTION_SET_UPGRADE_AUTHORITY, &rest);
+
+        let decree = SetUpgradeAuthorityDecree::parse(&payload).unwrap();
+        assert_eq!(decree.new_authority(), [0u8; 32]);
+    }
+
+    #[test]
+    fn set_upgrade_authority_decree_rejects_wrong_action() {
+        let mut rest = vec![0u8, 1];
+        rest.extend_from_slice(&[7u8; 32]);
+        let payload = governance_payload(ACTION_CLOSE_BUFFER, &rest);
+
+        assert!(SetUpgradeAuthorityDecree::parse(&payload).is_none());
+    }
+
+    #[test]
+    fn close_buffer_decree_round_trips() {
+        let buffer = [9u8; 32];
+        let mut rest = vec![0u8, 1];
+        rest.extend_from_slice(&buffer);
+        let payload = governance_payload(ACTION_CLOSE_BUFFER, &rest);
+
+        let decree = CloseBufferDecree::parse(&payload).unwrap();
+        assert_eq!(decree.chain(), 1);
+        assert_eq!(decree.buffer(), buffer);
+    }
+
+    #[test]
+    fn update_governance_source_decree_round_trips() {
+        let new_emitter = [3u8; 32];
+        let mut rest = vec![0u8, 1, 0u8, 2]; // chain = 1, new_governance_chain = 2
+        rest.extend_from_slice(&new_emitter);
+        let payload = governance_payload(ACTION_UPDATE_GOVERNANCE_SOURCE, &rest);
+
+        let decree = UpdateGovernanceSourceDecree::parse(&payload).unwrap();
+        assert_eq!(decree.chain(), 1);
+        assert_eq!(decree.new_governance_chain(), 2);
+        assert_eq!(decree.new_governance_emitter(), new_emitter);
+    }
+
+    #[test]
+    fn update_governance_source_decree_rejects_wrong_length() {
+        let payload = governance_payload(ACTION_UPDATE_GOVERNANCE_SOURCE, &[0u8, 1]);
+        assert!(UpdateGovernanceSourceDecree::parse(&payload).is_none());
+    }
+}
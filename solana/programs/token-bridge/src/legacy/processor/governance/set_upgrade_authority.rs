@@ -0,0 +1,202 @@
+use super::{common::authoritative_config, payload::SetUpgradeAuthorityDecree};
+use crate::{
+    constants::UPGRADE_SEED_PREFIX, error::TokenBridgeError, legacy::instruction::EmptyArgs,
+    state::Config,
+};
+use anchor_lang::prelude::*;
+use core_bridge_program::sdk as core_bridge;
+use solana_program::{bpf_loader_upgradeable, program::invoke_signed};
+
+#[derive(Accounts)]
+pub struct SetUpgradeAuthority<'info> {
+    #[account(mut)]
+    payer: Signer<'info>,
+
+    /// VAA account, which may either be the new EncodedVaa account or legacy PostedVaaV1
+    /// account.
+    ///
+    /// CHECK: This account will be read via zero-copy deserialization in the instruction
+    /// handler, which will determine which type of VAA account is being used. If this account
+    /// is the legacy PostedVaaV1 account, its PDA address will be verified by this zero-copy
+    /// reader.
+    #[account(owner = core_bridge::id())]
+    vaa: AccountInfo<'info>,
+
+    /// Claim account (mut), which acts as replay protection after consuming data from the VAA
+    /// account.
+    ///
+    /// Seeds: [emitter_address, emitter_chain, sequence],
+    /// seeds::program = token_bridge_program.
+    ///
+    /// CHECK: This account is created via [claim_vaa](core_bridge_program::sdk::claim_vaa).
+    /// This account can only be created once for this VAA.
+    #[account(mut)]
+    claim: AccountInfo<'info>,
+
+    /// CHECK: This is the current upgrade authority for this program. We verify this PDA
+    /// address here out of convenience to get the PDA bump seed to invoke the BPF Loader
+    /// Upgradeable program.
+    #[account(
+        seeds = [UPGRADE_SEED_PREFIX],
+        bump,
+    )]
+    upgrade_authority: AccountInfo<'info>,
+
+    /// New upgrade authority encoded in the governance VAA. Must sign so that the BPF Loader
+    /// Upgradeable program's checked set-authority instruction can confirm the new authority
+    /// actually controls the key it is being handed. Absent when the governance decree encodes
+    /// the "zero" authority, i.e. the request to make this program immutable.
+    new_authority: Option<Signer<'info>>,
+
+    /// Token Bridge program data needed for BPF Loader Upgradeable program.
+    ///
+    /// CHECK: BPF Loader Upgradeable program needs this account to change the program's
+    /// upgrade authority.
+    #[account(
+        mut,
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = solana_program::bpf_loader_upgradeable::id(),
+    )]
+    program_data: AccountInfo<'info>,
+
+    /// BPF Loader Upgradeable program.
+    ///
+    /// CHECK: In order to change this program's upgrade authority, we need to invoke the BPF
+    /// Loader Upgradeable program.
+    #[account(address = solana_program::bpf_loader_upgradeable::id())]
+    bpf_loader_upgradeable_program: AccountInfo<'info>,
+
+    /// Program configuration account, which stores the governance chain/emitter this program
+    /// trusts.
+    ///
+    /// CHECK: This account may still be in its pre-migration layout, which only stored
+    /// `core_bridge_program`. [Config::migrate_and_load] brings it up to date in place before we
+    /// read the governance chain/emitter from it.
+    #[account(
+        mut,
+        seeds = [Config::SEED_PREFIX],
+        bump,
+    )]
+    config: AccountInfo<'info>,
+
+    system_program: Program<'info, System>,
+}
+
+impl<'info> core_bridge::legacy::ProcessLegacyInstruction<'info, EmptyArgs>
+    for SetUpgradeAuthority<'info>
+{
+    const LOG_IX_NAME: &'static str = "LegacySetUpgradeAuthority";
+
+    const ANCHOR_IX_FN: fn(Context<Self>, EmptyArgs) -> Result<()> = set_upgrade_authority;
+}
+
+impl<'info> SetUpgradeAuthority<'info> {
+    fn constraints(ctx: &Context<Self>) -> Result<()> {
+        let vaa_acc_info = &ctx.accounts.vaa;
+        let vaa = core_bridge::VaaAccount::load(vaa_acc_info)?;
+
+        // `authoritative_config` below is what authorizes this VAA -- it checks the VAA's
+        // emitter chain/address against `Config`, which is the actual, governance-updatable
+        // source of truth (see `update_governance_source.rs`). `SetUpgradeAuthority` is not one
+        // of the actions `crate::processor::require_valid_governance_vaa`'s payload parser
+        // understands anyway, so the decree itself is decoded straight from the VAA payload
+        // below.
+        let decree = SetUpgradeAuthorityDecree::parse(vaa.payload())
+            .ok_or(error!(TokenBridgeError::InvalidGovernanceAction))?;
+
+        // Make sure that the new upgrade authority is intended for this network.
+        require_eq!(
+            decree.chain(),
+            core_bridge::SOLANA_CHAIN,
+            TokenBridgeError::GovernanceForAnotherChain
+        );
+
+        authoritative_config(
+            &ctx.accounts.config,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            &vaa,
+        )?;
+
+        // Unless the decree asks us to relinquish upgrade authority altogether, the new
+        // authority must be present and must match the signer we were given. When the decree
+        // does encode the "zero" immutable authority, no `new_authority` signer may be supplied
+        // either -- otherwise a guardian-signed "make immutable" VAA could be replayed with an
+        // attacker-controlled `new_authority` account and hand over upgrade control instead of
+        // relinquishing it.
+        let new_authority = Pubkey::from(decree.new_authority());
+        if new_authority != Pubkey::default() {
+            let signer = ctx
+                .accounts
+                .new_authority
+                .as_ref()
+                .ok_or(error!(TokenBridgeError::ImplementationMismatch))?;
+            require_keys_eq!(
+                signer.key(),
+                new_authority,
+                TokenBridgeError::ImplementationMismatch
+            );
+        } else {
+            require!(
+                ctx.accounts.new_authority.is_none(),
+                TokenBridgeError::ImplementationMismatch
+            );
+        }
+
+        // Done.
+        Ok(())
+    }
+}
+
+/// Processor for set-upgrade-authority governance decrees. This instruction handler invokes the
+/// BPF Loader Upgradeable program to either rotate this program's upgrade authority to a new
+/// key, or (when the decree encodes the zero authority) relinquish upgrade authority entirely,
+/// making this program immutable.
+#[access_control(SetUpgradeAuthority::constraints(&ctx))]
+fn set_upgrade_authority(ctx: Context<SetUpgradeAuthority>, _args: EmptyArgs) -> Result<()> {
+    let vaa = core_bridge::VaaAccount::load(&ctx.accounts.vaa).unwrap();
+
+    // Create the claim account to provide replay protection. Because this instruction creates this
+    // account every time it is executed, this account cannot be created again with this emitter
+    // address, chain and sequence combination.
+    core_bridge::claim_vaa(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            core_bridge::ClaimVaa {
+                claim: ctx.accounts.claim.to_account_info(),
+                payer: ctx.accounts.payer.to_account_info(),
+            },
+        ),
+        &crate::ID,
+        &vaa,
+        None,
+    )?;
+
+    // Whether we invoke the checked or unchecked instruction is determined by the governance
+    // decree itself, not by which accounts happen to have been passed in -- `constraints` has
+    // already verified that the `new_authority` account (or lack thereof) matches this decree.
+    let decree = SetUpgradeAuthorityDecree::parse(vaa.payload()).unwrap();
+    let new_authority = Pubkey::from(decree.new_authority());
+    let ix = if new_authority == Pubkey::default() {
+        // A zero new-authority means governance wants this program to become immutable.
+        bpf_loader_upgradeable::set_upgrade_authority(
+            &crate::ID,
+            &ctx.accounts.upgrade_authority.key(),
+            None,
+        )
+    } else {
+        bpf_loader_upgradeable::set_upgrade_authority_checked(
+            &crate::ID,
+            &ctx.accounts.upgrade_authority.key(),
+            &new_authority,
+        )
+    };
+
+    invoke_signed(
+        &ix,
+        &ctx.accounts.to_account_infos(),
+        &[&[UPGRADE_SEED_PREFIX, &[ctx.bumps["upgrade_authority"]]]],
+    )
+    .map_err(Into::into)
+}
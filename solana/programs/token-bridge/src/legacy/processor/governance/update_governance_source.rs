@@ -0,0 +1,124 @@
+use super::{common::authoritative_config, payload::UpdateGovernanceSourceDecree};
+use crate::{error::TokenBridgeError, legacy::instruction::EmptyArgs, state::Config};
+use anchor_lang::prelude::*;
+use core_bridge_program::sdk as core_bridge;
+
+#[derive(Accounts)]
+pub struct UpdateGovernanceSource<'info> {
+    #[account(mut)]
+    payer: Signer<'info>,
+
+    /// VAA account, which may either be the new EncodedVaa account or legacy PostedVaaV1
+    /// account.
+    ///
+    /// CHECK: This account will be read via zero-copy deserialization in the instruction
+    /// handler, which will determine which type of VAA account is being used. If this account
+    /// is the legacy PostedVaaV1 account, its PDA address will be verified by this zero-copy
+    /// reader.
+    #[account(owner = core_bridge::id())]
+    vaa: AccountInfo<'info>,
+
+    /// Claim account (mut), which acts as replay protection after consuming data from the VAA
+    /// account.
+    ///
+    /// Seeds: [emitter_address, emitter_chain, sequence],
+    /// seeds::program = token_bridge_program.
+    ///
+    /// CHECK: This account is created via [claim_vaa](core_bridge_program::sdk::claim_vaa).
+    /// This account can only be created once for this VAA.
+    #[account(mut)]
+    claim: AccountInfo<'info>,
+
+    /// Program configuration account, which stores the governance chain/emitter this program
+    /// trusts.
+    ///
+    /// CHECK: This account may still be in its pre-migration layout, which only stored
+    /// `core_bridge_program`. [Config::migrate_and_load] brings it up to date in place before we
+    /// read the governance chain/emitter from it.
+    #[account(
+        mut,
+        seeds = [Config::SEED_PREFIX],
+        bump,
+    )]
+    config: AccountInfo<'info>,
+
+    system_program: Program<'info, System>,
+}
+
+impl<'info> core_bridge::legacy::ProcessLegacyInstruction<'info, EmptyArgs>
+    for UpdateGovernanceSource<'info>
+{
+    const LOG_IX_NAME: &'static str = "LegacyUpdateGovernanceSource";
+
+    const ANCHOR_IX_FN: fn(Context<Self>, EmptyArgs) -> Result<()> = update_governance_source;
+}
+
+impl<'info> UpdateGovernanceSource<'info> {
+    fn constraints(ctx: &Context<Self>) -> Result<UpdateGovernanceSourceDecree> {
+        let vaa_acc_info = &ctx.accounts.vaa;
+        let vaa = core_bridge::VaaAccount::load(vaa_acc_info)?;
+
+        // Like `SetUpgradeAuthority` and `CloseBuffer` (and unlike `UpgradeContract`), this handler
+        // deliberately does not call `crate::processor::require_valid_governance_vaa`: that path
+        // authorizes against the
+        // compile-time `crate::constants::GOVERNANCE_*` values, which is exactly the thing this
+        // instruction exists to move on from. Once `Config` has been repointed by a prior
+        // `UpdateGovernanceSource`, the current governance source -- not the original deployment
+        // defaults -- must be the one that can repoint it again.
+        let decree = UpdateGovernanceSourceDecree::parse(vaa.payload())
+            .ok_or(error!(TokenBridgeError::InvalidGovernanceAction))?;
+
+        // Make sure that this governance source update is intended for this network.
+        require_eq!(
+            decree.chain(),
+            core_bridge::SOLANA_CHAIN,
+            TokenBridgeError::GovernanceForAnotherChain
+        );
+
+        authoritative_config(
+            &ctx.accounts.config,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            &vaa,
+        )?;
+
+        Ok(decree)
+    }
+}
+
+/// Processor for update-governance-source governance decrees. Repoints the governance
+/// chain/emitter that every other governance handler trusts `Config` to hold, so the
+/// authorization `Config` backs is real and not a permanent mirror of this program's
+/// compile-time defaults.
+#[access_control(UpdateGovernanceSource::constraints(&ctx))]
+fn update_governance_source(ctx: Context<UpdateGovernanceSource>, _args: EmptyArgs) -> Result<()> {
+    let vaa = core_bridge::VaaAccount::load(&ctx.accounts.vaa).unwrap();
+    let decree = UpdateGovernanceSourceDecree::parse(vaa.payload()).unwrap();
+
+    // Create the claim account to provide replay protection. Because this instruction creates this
+    // account every time it is executed, this account cannot be created again with this emitter
+    // address, chain and sequence combination.
+    core_bridge::claim_vaa(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            core_bridge::ClaimVaa {
+                claim: ctx.accounts.claim.to_account_info(),
+                payer: ctx.accounts.payer.to_account_info(),
+            },
+        ),
+        &crate::ID,
+        &vaa,
+        None,
+    )?;
+
+    let mut config = Config::migrate_and_load(
+        &ctx.accounts.config,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+    )?;
+    config.governance_chain = decree.new_governance_chain();
+    config.governance_emitter = Pubkey::from(decree.new_governance_emitter());
+    config.save(&ctx.accounts.config)?;
+
+    Ok(())
+}
@@ -1,9 +1,75 @@
+use super::common::authoritative_config;
 use crate::{
     constants::UPGRADE_SEED_PREFIX, error::TokenBridgeError, legacy::instruction::EmptyArgs,
+    state::Config,
 };
 use anchor_lang::prelude::*;
 use core_bridge_program::sdk as core_bridge;
-use solana_program::{bpf_loader_upgradeable, program::invoke_signed};
+use solana_program::{
+    bpf_loader_upgradeable,
+    hash::hash,
+    program::{invoke, invoke_signed},
+};
+
+/// Length of the `UpgradeableLoaderState::Buffer` header that precedes a buffer account's
+/// executable bytes.
+const BUFFER_METADATA_LEN: usize = 37;
+
+/// Length of the `UpgradeableLoaderState::ProgramData` header that precedes a program data
+/// account's executable bytes.
+const PROGRAM_DATA_METADATA_LEN: usize = 45;
+
+/// Minimum number of slots that must elapse between successful governance upgrades. Bounds how
+/// quickly a compromised governance key can re-upgrade this program.
+const UPGRADE_COOLDOWN_SLOTS: u64 = 1_500;
+
+/// Whether enough slots have elapsed since `last_upgrade_slot` to allow another upgrade.
+/// `last_upgrade_slot == 0` means no upgrade has ever been recorded (a fresh `initialize`, or the
+/// first upgrade after migrating from the legacy `Config` layout) rather than an upgrade that
+/// genuinely happened at slot 0, so the cooldown does not apply yet -- otherwise every new
+/// deployment would reject its first upgrade until the cluster reached slot
+/// `UPGRADE_COOLDOWN_SLOTS`.
+fn cooldown_elapsed(current_slot: u64, last_upgrade_slot: u64) -> bool {
+    last_upgrade_slot == 0 || current_slot.saturating_sub(last_upgrade_slot) >= UPGRADE_COOLDOWN_SLOTS
+}
+
+/// Computes the `program_data` account length required to hold `buffer_len` bytes of staged
+/// executable. `program_data` carries the (slightly larger) `ProgramData` header in place of
+/// `Buffer`'s, so this isn't simply `buffer_len`.
+fn required_program_data_len(buffer_len: usize) -> usize {
+    buffer_len.saturating_sub(BUFFER_METADATA_LEN) + PROGRAM_DATA_METADATA_LEN
+}
+
+/// Trusted length of the code hashed into `old_code_hash`, given the code length `Config`
+/// recorded the last time this program was upgraded (`Config::last_code_len`) and the number of
+/// code bytes actually available in `program_data` right now.
+///
+/// The length is not recovered by parsing the deployed ELF (e.g. assuming its section header
+/// table comes last in the file), since that is a toolchain convention, not an ELF invariant,
+/// and does not hold for every binary (e.g. a stripped one with `e_shnum == 0`).
+/// `Config::last_code_len` instead carries forward
+/// the exact length `new_code_hash` hashed on the previous upgrade (see the `config.last_code_len
+/// = new_code_len` assignment in `upgrade_contract` below), so by construction this upgrade's
+/// `old_code_hash` always hashes the same bytes the prior upgrade's `new_code_hash` did -- no
+/// parsing of the executable required. Falls back to the full available length when `Config` has
+/// never recorded one (e.g. a deployment that predates this field, or an account that was never
+/// upgraded through this handler).
+fn trusted_code_len(last_code_len: u64, available_len: usize) -> usize {
+    match usize::try_from(last_code_len) {
+        Ok(len) if len <= available_len => len,
+        _ => available_len,
+    }
+}
+
+/// Emitted once a governance upgrade has been executed, so off-chain watchers can confirm that
+/// the governed buffer's code is what actually ended up deployed.
+#[event]
+pub struct ContractUpgraded {
+    pub sequence: u64,
+    pub old_code_hash: [u8; 32],
+    pub new_code_hash: [u8; 32],
+    pub implementation: Pubkey,
+}
 
 #[derive(Accounts)]
 pub struct UpgradeContract<'info> {
@@ -87,6 +153,19 @@ pub struct UpgradeContract<'info> {
     #[account(address = solana_program::bpf_loader_upgradeable::id())]
     bpf_loader_upgradeable_program: AccountInfo<'info>,
 
+    /// Program configuration account, which stores the governance chain/emitter this program
+    /// trusts and the slot of the last successful upgrade.
+    ///
+    /// CHECK: This account may still be in its pre-migration layout, which only stored
+    /// `core_bridge_program`. [Config::migrate_and_load] brings it up to date in place before we
+    /// read governance chain/emitter and cooldown state from it.
+    #[account(
+        mut,
+        seeds = [Config::SEED_PREFIX],
+        bump,
+    )]
+    config: AccountInfo<'info>,
+
     system_program: Program<'info, System>,
 }
 
@@ -123,6 +202,22 @@ impl<'info> UpgradeContract<'info> {
             TokenBridgeError::ImplementationMismatch
         );
 
+        // `Config` is authoritative for the governance chain/emitter this deployment trusts
+        // (governance can repoint it via `UpdateGovernanceSource`, see
+        // `update_governance_source.rs`) and for the cooldown between upgrades.
+        let config = authoritative_config(
+            &ctx.accounts.config,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            &vaa,
+        )?;
+
+        let slot = Clock::get()?.slot;
+        require!(
+            cooldown_elapsed(slot, config.last_upgrade_slot),
+            TokenBridgeError::UpgradeCooldownNotElapsed
+        );
+
         // Done.
         Ok(())
     }
@@ -150,6 +245,48 @@ fn upgrade_contract(ctx: Context<UpgradeContract>, _args: EmptyArgs) -> Result<(
         None,
     )?;
 
+    // Hash the currently deployed executable and the buffer's staged executable before we touch
+    // either account, so indexers watching for [ContractUpgraded] can cross-check that this
+    // governance VAA's sequence really installed the code it claims to.
+    let mut config = Config::migrate_and_load(
+        &ctx.accounts.config,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+    )?;
+    let old_code_hash = {
+        let data = ctx.accounts.program_data.try_borrow_data()?;
+        let start = PROGRAM_DATA_METADATA_LEN.min(data.len());
+        let code = &data[start..];
+        hash(&code[..trusted_code_len(config.last_code_len, code.len())]).to_bytes()
+    };
+    let new_code_len;
+    let new_code_hash = {
+        let data = ctx.accounts.buffer.try_borrow_data()?;
+        let start = BUFFER_METADATA_LEN.min(data.len());
+        new_code_len = data.len() - start;
+        hash(&data[start..]).to_bytes()
+    };
+
+    // The buffer account's data is the `UpgradeableLoaderState::Buffer` header followed by the
+    // new executable bytes, while `program_data` holds the (slightly larger) `ProgramData`
+    // header followed by the currently deployed executable. If the incoming executable no
+    // longer fits in the space already allocated for `program_data`, the loader's `Upgrade`
+    // instruction fails with an account-too-small error. Extend `program_data` first so a large
+    // governance upgrade cannot silently revert.
+    let required_len = required_program_data_len(ctx.accounts.buffer.data_len());
+    let program_data_len = ctx.accounts.program_data.data_len();
+    if program_data_len < required_len {
+        let additional_bytes = (required_len - program_data_len) as u32;
+        invoke(
+            &bpf_loader_upgradeable::extend_program(
+                &crate::ID,
+                Some(&ctx.accounts.payer.key()),
+                additional_bytes,
+            ),
+            &ctx.accounts.to_account_infos(),
+        )?;
+    }
+
     // Finally upgrade.
     invoke_signed(
         &bpf_loader_upgradeable::upgrade(
@@ -160,6 +297,94 @@ fn upgrade_contract(ctx: Context<UpgradeContract>, _args: EmptyArgs) -> Result<(
         ),
         &ctx.accounts.to_account_infos(),
         &[&[UPGRADE_SEED_PREFIX, &[ctx.bumps["upgrade_authority"]]]],
-    )
-    .map_err(Into::into)
+    )?;
+
+    // Record this upgrade's slot (for the cooldown) and code length (so the next upgrade's
+    // old_code_hash trims program_data to exactly what this upgrade's new_code_hash hashed).
+    config.last_upgrade_slot = Clock::get()?.slot;
+    config.last_code_len = new_code_len as u64;
+    config.save(&ctx.accounts.config)?;
+
+    emit!(ContractUpgraded {
+        sequence: vaa.sequence(),
+        old_code_hash,
+        new_code_hash,
+        implementation: ctx.accounts.buffer.key(),
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn required_program_data_len_accounts_for_header_size_difference() {
+        // `program_data`'s header is 8 bytes larger than `buffer`'s, so the same code requires
+        // 8 more bytes of account space once it's deployed.
+        assert_eq!(
+            required_program_data_len(BUFFER_METADATA_LEN + 100),
+            PROGRAM_DATA_METADATA_LEN + 100
+        );
+    }
+
+    #[test]
+    fn required_program_data_len_saturates_on_undersized_buffer() {
+        // A buffer smaller than its own header is malformed, but this must not underflow.
+        assert_eq!(required_program_data_len(0), PROGRAM_DATA_METADATA_LEN);
+    }
+
+    #[test]
+    fn cooldown_elapsed_skips_check_on_fresh_config() {
+        // `last_upgrade_slot == 0` must not reject the first upgrade just because the cluster
+        // hasn't yet reached slot `UPGRADE_COOLDOWN_SLOTS`.
+        assert!(cooldown_elapsed(0, 0));
+        assert!(cooldown_elapsed(1, 0));
+        assert!(cooldown_elapsed(UPGRADE_COOLDOWN_SLOTS - 1, 0));
+    }
+
+    #[test]
+    fn cooldown_elapsed_rejects_before_the_window_closes() {
+        assert!(!cooldown_elapsed(
+            1_000 + UPGRADE_COOLDOWN_SLOTS - 1,
+            1_000
+        ));
+    }
+
+    #[test]
+    fn cooldown_elapsed_accepts_once_the_window_closes() {
+        assert!(cooldown_elapsed(1_000 + UPGRADE_COOLDOWN_SLOTS, 1_000));
+    }
+
+    #[test]
+    fn trusted_code_len_uses_recorded_length() {
+        // program_data may carry trailing zero-fill padding beyond the deployed code; the
+        // recorded length, not the full available length, is what must be hashed.
+        assert_eq!(trusted_code_len(123, 1_000), 123);
+    }
+
+    #[test]
+    fn trusted_code_len_falls_back_when_unset_or_stale() {
+        // `last_code_len == 0` covers a deployment that predates this field (or was never
+        // upgraded through this handler).
+        assert_eq!(trusted_code_len(0, 1_000), 1_000);
+        // A recorded length longer than what's actually available can't be trusted either --
+        // fall back rather than reading out of bounds.
+        assert_eq!(trusted_code_len(2_000, 1_000), 1_000);
+    }
+
+    #[test]
+    fn new_code_len_becomes_next_upgrades_trusted_len() {
+        // This is the invariant the whole scheme exists for: upgrade N's new_code_hash and
+        // upgrade N+1's old_code_hash must hash the same bytes. Recording new_code_len verbatim
+        // as the next upgrade's last_code_len guarantees trusted_code_len reproduces it exactly,
+        // with no ELF parsing involved.
+        let buffer_data_len = BUFFER_METADATA_LEN + 4_096;
+        let new_code_len = buffer_data_len - BUFFER_METADATA_LEN;
+
+        let program_data_len = PROGRAM_DATA_METADATA_LEN + new_code_len + 512; // extra padding
+        let available = program_data_len - PROGRAM_DATA_METADATA_LEN;
+        assert_eq!(trusted_code_len(new_code_len as u64, available), new_code_len);
+    }
 }
@@ -33,10 +33,13 @@ impl<'info> core_bridge::legacy::ProcessLegacyInstruction<'info, InitializeArgs>
 }
 
 fn initialize(ctx: Context<Initialize>, _args: InitializeArgs) -> Result<()> {
-    // NOTE: This config account is pointless and is never used in any of the instruction handlers.
     ctx.accounts.config.set_inner(
         Config {
             core_bridge_program: core_bridge_program::ID,
+            governance_chain: crate::constants::GOVERNANCE_CHAIN,
+            governance_emitter: Pubkey::from(crate::constants::GOVERNANCE_EMITTER),
+            last_upgrade_slot: 0,
+            last_code_len: 0,
         }
         .into(),
     );
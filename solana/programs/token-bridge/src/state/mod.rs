@@ -0,0 +1,102 @@
+use crate::error::TokenBridgeError;
+use anchor_lang::prelude::*;
+use core_bridge_program::sdk::legacy::LegacyAnchorized;
+
+/// Token Bridge program configuration.
+///
+/// NOTE: Deployments created before governance upgrades carried a cooldown only persisted
+/// [Config::core_bridge_program] in this account. [Config::migrate_and_load] brings such
+/// accounts up to the current layout in place the first time a governance instruction touches
+/// them, so there is no separate migration instruction to run.
+#[derive(Debug, Default, AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct Config {
+    pub core_bridge_program: Pubkey,
+
+    /// Wormhole chain ID that governance VAAs for this program must be emitted from.
+    pub governance_chain: u16,
+
+    /// Emitter address that governance VAAs for this program must originate from.
+    pub governance_emitter: Pubkey,
+
+    /// Slot of the last successful governance upgrade, used to enforce a cooldown between
+    /// upgrades.
+    pub last_upgrade_slot: u64,
+
+    /// Length, in bytes, of the executable code hashed into the last `ContractUpgraded.new_code_hash`
+    /// this program emitted. The next upgrade trims `program_data` to this many bytes before
+    /// hashing `old_code_hash`, rather than trying to recover the deployed length by parsing the
+    /// ELF. Zero until the first upgrade processed through `upgrade_contract` records it.
+    pub last_code_len: u64,
+}
+
+impl Config {
+    pub const SEED_PREFIX: &'static [u8] = b"config";
+
+    /// Size (in bytes) of the original account layout, which stored only
+    /// [Config::core_bridge_program]. Accounts still at this size are migrated in place by
+    /// [Config::migrate_and_load].
+    pub const LEGACY_LEN: usize = 32;
+
+    /// Loads this account, migrating it in place from the legacy layout if necessary. Migration
+    /// reallocates the account to [Config::INIT_SPACE], topping up rent from `payer`, and seeds
+    /// the new fields with the governance chain/emitter this program has always trusted and a
+    /// zero `last_upgrade_slot` (i.e. no cooldown in effect yet).
+    pub fn migrate_and_load<'info>(
+        account_info: &AccountInfo<'info>,
+        payer: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+    ) -> Result<Self> {
+        if account_info.data_len() != Self::LEGACY_LEN {
+            // Already migrated: this account was created (and last written) as
+            // `LegacyAnchorized<Config>`, so it must be read back through that same codec rather
+            // than a raw Borsh deserialization of `Config`.
+            let data = account_info.try_borrow_data()?;
+            let wrapped = LegacyAnchorized::<Self>::try_deserialize(&mut &data[..])?;
+            return Ok((*wrapped).clone());
+        }
+
+        let core_bridge_program = {
+            let data = account_info.try_borrow_data()?;
+            Pubkey::try_from_slice(&data)
+                .map_err(|_| error!(TokenBridgeError::InvalidConfig))?
+        };
+
+        let rent = Rent::get()?;
+        let additional_rent = rent
+            .minimum_balance(Self::INIT_SPACE)
+            .saturating_sub(account_info.lamports());
+        if additional_rent > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    system_program.clone(),
+                    anchor_lang::system_program::Transfer {
+                        from: payer.clone(),
+                        to: account_info.clone(),
+                    },
+                ),
+                additional_rent,
+            )?;
+        }
+        account_info.realloc(Self::INIT_SPACE, false)?;
+
+        let migrated = Self {
+            core_bridge_program,
+            governance_chain: crate::constants::GOVERNANCE_CHAIN,
+            governance_emitter: Pubkey::from(crate::constants::GOVERNANCE_EMITTER),
+            last_upgrade_slot: 0,
+            last_code_len: 0,
+        };
+        migrated.save(account_info)?;
+
+        Ok(migrated)
+    }
+
+    /// Writes `self` back to `account_info` using the same [LegacyAnchorized] codec
+    /// [Config::migrate_and_load] reads through, so a raw Borsh write can't desynchronize the
+    /// account from the framing Anchor expects when it is next loaded as
+    /// `Account<LegacyAnchorized<Config>>`.
+    pub fn save<'info>(&self, account_info: &AccountInfo<'info>) -> Result<()> {
+        let wrapped: LegacyAnchorized<Self> = self.clone().into();
+        wrapped.try_serialize(&mut &mut account_info.try_borrow_mut_data()?[..])
+    }
+}